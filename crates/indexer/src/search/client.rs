@@ -1,4 +1,11 @@
-use std::{collections::BinaryHeap, sync::Arc, time::Duration};
+use std::{
+    collections::BinaryHeap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use crossbeam::queue::SegQueue;
 use indexer_core::{
@@ -7,7 +14,8 @@ use indexer_core::{
     meilisearch::{
         self,
         client::Client as MeiliClient,
-        tasks::{DocumentAddition, ProcessedTask, Task, TaskType},
+        settings::Settings,
+        tasks::{DocumentAddition, DocumentDeletion, ProcessedTask, Task, TaskType},
     },
     util,
 };
@@ -34,10 +42,220 @@ pub struct Args {
     #[clap(long, short = 'n', env)]
     dry_run: bool,
 
+    /// Number of seconds to wait after the first document arrives before
+    /// flushing, to allow more documents to accumulate
+    #[clap(long, env, default_value_t = 1)]
+    debounce_duration_sec: u64,
+
+    /// Maximum number of documents to send to a single index in one
+    /// `add_or_replace` call
+    #[clap(long, env, default_value_t = 1000)]
+    max_documents_per_batch: usize,
+
+    /// Maximum number of index groups to dispatch in a single upsert tick
+    #[clap(long, env, default_value_t = 10)]
+    max_tasks_per_batch: usize,
+
+    /// Maximum number of times to retry a batch whose Meilisearch task fails
+    #[clap(long, env, default_value_t = 3)]
+    max_task_retries: u32,
+
+    /// Base delay in seconds for exponential backoff between batch retries
+    #[clap(long, env, default_value_t = 2)]
+    task_retry_base_delay_sec: u64,
+
+    /// How many Meilisearch nodes are expected to acknowledge a batch when
+    /// running with follower nodes; a shortfall is logged as a warning and
+    /// does not change the batch's tracked status, which follows the leader
+    #[clap(long, env, value_enum, default_value_t = WriteConsistency::One)]
+    write_consistency: WriteConsistency,
+
     #[clap(flatten)]
     meili: meilisearch::Args,
 }
 
+/// How many nodes are expected to report a succeeded task for a batch.
+///
+/// Falling short of this is only logged; the status tracked for a batch
+/// follows the leader's task alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WriteConsistency {
+    /// Only the leader needs to succeed
+    One,
+    /// A majority of nodes (the leader plus its followers) must succeed
+    Quorum,
+    /// Every node, leader and followers alike, must succeed
+    All,
+}
+
+impl WriteConsistency {
+    /// The number of nodes (out of `1 + num_followers`) expected to
+    /// acknowledge a batch; `Client::replicate` warns when fewer do
+    fn required(self, num_followers: usize) -> usize {
+        match self {
+            Self::One => 1,
+            Self::Quorum => (num_followers + 1) / 2 + 1,
+            Self::All => num_followers + 1,
+        }
+    }
+}
+
+/// The last known lifecycle state of a dispatched Meilisearch task, as
+/// tracked by this client independent of the upstream task payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// The task is queued on the Meilisearch server but not yet processing
+    Enqueued,
+    /// The task is being processed by the Meilisearch server
+    Processing,
+    /// The task completed successfully
+    Succeeded,
+    /// The task failed and is queued for retry, or retries were exhausted
+    Failed,
+}
+
+/// The format of a source handed to [`Client::bulk_load`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    /// A single top-level JSON array of documents
+    Json,
+    /// One JSON document per line
+    NdJson,
+    /// A header row followed by comma-separated records
+    Csv,
+}
+
+impl ContentType {
+    /// Resolve a content type from a MIME type, as would be supplied
+    /// alongside an uploaded bulk-load source
+    #[must_use]
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        match mime {
+            "application/json" => Some(Self::Json),
+            "application/x-ndjson" => Some(Self::NdJson),
+            "text/csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// A dispatched batch, tracked so its outcome can be observed and, if it
+/// fails, replayed.
+///
+/// Identified by a stable id rather than a Meilisearch task uid, since a
+/// retry dispatches a brand new uid for the same logical batch; `current_uid`
+/// is updated on each (re)dispatch so polling always targets the live task
+/// while `task_status` can keep resolving the *original* uid to this entry.
+#[derive(Debug)]
+struct TrackedTask {
+    idx: String,
+    ops: Vec<Operation>,
+    status: TaskStatus,
+    retries: u32,
+    current_uid: u32,
+    /// Set once a dispatched task is observed to have failed and a retry is
+    /// scheduled; cleared once that retry is dispatched. Polled from the
+    /// upsert worker's main loop instead of sleeping inline, so a backoff on
+    /// one batch can't stall the whole pipeline.
+    retry_at: Option<Instant>,
+    /// Set once the task reaches a terminal state (succeeded, or failed with
+    /// retries exhausted), so `prune_completed_tasks` can evict it after
+    /// `TRACKED_TASK_RETENTION` has elapsed.
+    completed_at: Option<Instant>,
+}
+
+fn task_uid(task: &Task) -> u32 {
+    match task {
+        Task::Enqueued { content } => content.uid,
+        Task::Processing { content } => content.uid,
+        Task::Succeeded { content } => content.uid,
+        Task::Failed { content } => content.uid,
+    }
+}
+
+/// Build the dispatch future for a homogeneous batch of operations against
+/// one client. `idx`/`ops` are cloned into the returned future rather than
+/// borrowed, so this can be called repeatedly (once per leader/follower) from
+/// behind a `for<'c> Fn(&'c MeiliClient) -> ...` closure without fighting the
+/// borrow checker over the closure's own captures.
+fn dispatch_ops(
+    client: &MeiliClient,
+    idx: String,
+    ops: Vec<Operation>,
+) -> futures_util::future::BoxFuture<'_, Result<Task>> {
+    use futures_util::FutureExt;
+
+    async move {
+        if let [Operation::SettingsUpdate(settings)] = ops.as_slice() {
+            client
+                .index(&idx)
+                .set_settings(settings)
+                .await
+                .context("Failed to submit settings update")
+        } else if matches!(ops.first(), Some(Operation::Delete(_))) {
+            let keys: Vec<_> = ops
+                .into_iter()
+                .map(|op| match op {
+                    Operation::Delete(pk) => pk,
+                    _ => unreachable!("mixed operation kinds in a single dispatch"),
+                })
+                .collect();
+
+            client
+                .index(&idx)
+                .delete_documents(&keys)
+                .await
+                .context("Failed to submit deletion")
+        } else {
+            let docs: Vec<_> = ops
+                .into_iter()
+                .map(|op| match op {
+                    Operation::Upsert(doc) => doc,
+                    _ => unreachable!("mixed operation kinds in a single dispatch"),
+                })
+                .collect();
+
+            client
+                .index(&idx)
+                .add_or_replace(&docs, None)
+                .await
+                .context("Failed to submit upsert")
+        }
+    }
+    .boxed()
+}
+
+/// Key an operation by the index and document it affects, so a follower's
+/// replay buffer can coalesce repeated writes to the same document down to
+/// just the latest one instead of replaying them in order (and potentially
+/// regressing a document that was already caught up by a more recent direct
+/// write). Settings updates for an index share one key for the same reason.
+fn follower_buffer_key(idx: &str, op: &Operation) -> String {
+    let dedupe = match op {
+        Operation::Upsert(doc) => {
+            let value = serde_json::to_value(doc).unwrap_or(serde_json::Value::Null);
+            format!("doc:{}", value.get("id").unwrap_or(&value))
+        },
+        Operation::Delete(pk) => format!("doc:{pk}"),
+        Operation::SettingsUpdate(_) => "settings".to_owned(),
+    };
+
+    format!("{idx}:{dedupe}")
+}
+
+/// Maximum number of distinct keys buffered per follower before writes for
+/// not-yet-seen keys are dropped; already-buffered keys keep coalescing past
+/// this limit, since that can't grow the buffer any further.
+const MAX_FOLLOWER_BUFFER_ENTRIES: usize = 10_000;
+
+/// How long a terminal `TrackedTask` (succeeded, or failed with retries
+/// exhausted) is kept around after completion before being evicted from
+/// `tracked_tasks`/`uid_to_task`, so a long-running indexer doing continuous
+/// upserts doesn't grow those maps without bound. Chosen generously so
+/// `task_status` still has a reasonable window to resolve a just-completed
+/// task's original uid.
+const TRACKED_TASK_RETENTION: Duration = Duration::from_secs(60 * 10);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct UpsertTimingDatapoint {
     finished_at: DateTime<Utc>,
@@ -58,14 +276,37 @@ impl std::cmp::PartialOrd for UpsertTimingDatapoint {
     }
 }
 
+/// A single operation queued against an index, dispatched as part of the
+/// same batched, interval-paced pipeline as document upserts
+#[derive(Debug, Clone)]
+enum Operation {
+    /// Add or replace a document
+    Upsert(super::Document),
+    /// Remove a document by primary key
+    Delete(serde_json::Value),
+    /// Re-apply the index's settings
+    SettingsUpdate(Settings),
+}
+
 /// Wrapper for handling network logic
 #[derive(Debug)]
 pub struct Client {
     db: Pool,
     upsert_batch: usize,
-    upsert_queue: RwLock<SegQueue<(String, super::Document)>>,
+    upsert_queue: RwLock<SegQueue<(String, Operation)>>,
     upsert_timing_set: Mutex<BinaryHeap<UpsertTimingDatapoint>>,
     trigger_upsert: mpsc::Sender<()>,
+    debounce_since: Mutex<Option<Instant>>,
+    next_task_id: AtomicU64,
+    /// Maps every uid a tracked batch has ever been dispatched under
+    /// (including retries) to its stable task id, so a caller holding the
+    /// original uid can keep resolving status through retries.
+    uid_to_task: RwLock<HashMap<u32, u64>>,
+    tracked_tasks: RwLock<HashMap<u64, TrackedTask>>,
+    max_task_retries: u32,
+    task_retry_base_delay: Duration,
+    write_consistency: WriteConsistency,
+    follower_buffers: Mutex<Vec<HashMap<String, (String, Operation)>>>,
 }
 
 impl Client {
@@ -81,19 +322,46 @@ impl Client {
             upsert_batch,
             upsert_interval_sample_size,
             dry_run,
+            debounce_duration_sec,
+            max_documents_per_batch,
+            max_tasks_per_batch,
+            max_task_retries,
+            task_retry_base_delay_sec,
+            write_consistency,
             meili,
         } = args;
 
-        let meili = meili.into_client();
+        // The first node is the leader this process issues reads and waits
+        // against; any remaining nodes are followers kept in sync via
+        // replication rather than by re-indexing from Postgres.
+        let mut clients = meili.into_clients();
+        ensure!(!clients.is_empty(), "No Meilisearch node URLs configured");
+        let meili = clients.remove(0);
+        let followers = clients;
 
-        create_index(meili.clone(), "metadatas", "id")
+        create_index(meili.clone(), "metadatas", "id", Some(&metadatas_settings()))
             .await
             .context("failed to create metadatas index")?;
 
-        create_index(meili.clone(), "name_service", "id")
+        create_index(meili.clone(), "name_service", "id", None)
             .await
             .context("failed to create name service index")?;
 
+        for follower in &followers {
+            create_index(
+                follower.clone(),
+                "metadatas",
+                "id",
+                Some(&metadatas_settings()),
+            )
+            .await
+            .context("failed to create metadatas index on follower")?;
+
+            create_index(follower.clone(), "name_service", "id", None)
+                .await
+                .context("failed to create name service index on follower")?;
+        }
+
         let (trigger_upsert, upsert_rx) = mpsc::channel(1);
         let (stop_tx, stop_rx) = oneshot::channel();
 
@@ -103,13 +371,25 @@ impl Client {
             upsert_queue: RwLock::new(SegQueue::new()),
             upsert_timing_set: Mutex::new(BinaryHeap::new()),
             trigger_upsert,
+            debounce_since: Mutex::new(None),
+            next_task_id: AtomicU64::new(0),
+            uid_to_task: RwLock::new(HashMap::default()),
+            tracked_tasks: RwLock::new(HashMap::default()),
+            max_task_retries,
+            task_retry_base_delay: Duration::from_secs(task_retry_base_delay_sec),
+            write_consistency,
+            follower_buffers: Mutex::new(vec![HashMap::default(); followers.len()]),
         });
 
         let upsert_task = task::spawn(arc_self.clone().run_upserts(
             meili.clone(),
+            followers,
             upsert_interval_sample_size,
             upsert_batch,
             dry_run,
+            Duration::from_secs(debounce_duration_sec),
+            max_documents_per_batch,
+            max_tasks_per_batch,
             upsert_rx,
             stop_rx,
         ));
@@ -120,9 +400,13 @@ impl Client {
     async fn run_upserts(
         self: Arc<Self>,
         meili: MeiliClient,
+        followers: Vec<MeiliClient>,
         interval_sample_size: usize,
         batch_size: usize,
         dry_run: bool,
+        debounce_duration: Duration,
+        max_documents_per_batch: usize,
+        max_tasks_per_batch: usize,
         mut rx: mpsc::Receiver<()>,
         mut stop_rx: oneshot::Receiver<()>,
     ) {
@@ -130,9 +414,13 @@ impl Client {
             match self
                 .try_run_upserts(
                     meili.clone(),
+                    &followers,
                     interval_sample_size,
                     batch_size,
                     dry_run,
+                    debounce_duration,
+                    max_documents_per_batch,
+                    max_tasks_per_batch,
                     &mut rx,
                     &mut stop_rx,
                 )
@@ -176,7 +464,7 @@ impl Client {
                         _ => return None,
                     };
 
-                    // Reject outliers or non-upsert tasks
+                    // Reject outliers or non-upsert/non-delete tasks
                     match update_type {
                         TaskType::DocumentAddition {
                             details:
@@ -185,6 +473,13 @@ impl Client {
                                     ..
                                 }),
                         } if count >= batch_size / 2 => (),
+                        TaskType::DocumentDeletion {
+                            details:
+                                Some(DocumentDeletion {
+                                    deleted_documents: Some(count),
+                                    ..
+                                }),
+                        } if count >= batch_size / 2 => (),
                         _ => return None,
                     }
 
@@ -240,12 +535,431 @@ impl Client {
         Ok(interval)
     }
 
+    /// Poll Meilisearch for the status of every task this client has
+    /// dispatched and is still actively tracking (enqueued or processing),
+    /// scheduling a retry deadline for any that have failed, then dispatch
+    /// whatever retries have come due, then prune any tasks that have been
+    /// terminal for longer than `TRACKED_TASK_RETENTION`.
+    async fn reconcile_tracked_tasks(
+        &self,
+        meili: &MeiliClient,
+        followers: &[MeiliClient],
+    ) -> Result<()> {
+        let polling: Vec<(u64, u32)> = self
+            .tracked_tasks
+            .read()
+            .await
+            .iter()
+            .filter(|(_, t)| matches!(t.status, TaskStatus::Enqueued | TaskStatus::Processing))
+            .map(|(id, t)| (*id, t.current_uid))
+            .collect();
+
+        for (task_id, uid) in polling {
+            let task = match meili.get_task(uid).await {
+                Ok(task) => task,
+                Err(e) => {
+                    warn!("Failed to poll Meilisearch task {}: {}", uid, e);
+                    continue;
+                },
+            };
+
+            match task {
+                Task::Succeeded { .. } => {
+                    if let Some(t) = self.tracked_tasks.write().await.get_mut(&task_id) {
+                        t.status = TaskStatus::Succeeded;
+                        t.completed_at = Some(Instant::now());
+                    }
+                },
+                Task::Failed { .. } => {
+                    let mut tracked_tasks = self.tracked_tasks.write().await;
+                    if let Some(t) = tracked_tasks.get_mut(&task_id) {
+                        t.status = TaskStatus::Failed;
+
+                        if t.retries >= self.max_task_retries {
+                            error!(
+                                "Giving up on Meilisearch task {} for index {:?} after {} \
+                                 retries",
+                                uid, t.idx, t.retries
+                            );
+                            t.completed_at = Some(Instant::now());
+                        } else {
+                            t.retries += 1;
+                            let backoff = self.task_retry_base_delay * 2u32.pow(t.retries - 1);
+
+                            warn!(
+                                "Meilisearch task {} for index {:?} failed, retrying in {:?} \
+                                 (attempt {}/{})",
+                                uid, t.idx, backoff, t.retries, self.max_task_retries
+                            );
+
+                            t.retry_at = Some(Instant::now() + backoff);
+                        }
+                    }
+                },
+                Task::Enqueued { .. } => {
+                    if let Some(t) = self.tracked_tasks.write().await.get_mut(&task_id) {
+                        t.status = TaskStatus::Enqueued;
+                    }
+                },
+                Task::Processing { .. } => {
+                    if let Some(t) = self.tracked_tasks.write().await.get_mut(&task_id) {
+                        t.status = TaskStatus::Processing;
+                    }
+                },
+            }
+        }
+
+        self.dispatch_due_retries(meili, followers).await;
+        self.prune_completed_tasks().await;
+
+        Ok(())
+    }
+
+    /// Evict tracked tasks that reached a terminal state more than
+    /// `TRACKED_TASK_RETENTION` ago, from both `tracked_tasks` and
+    /// `uid_to_task`, so continuous upserts don't grow those maps without
+    /// bound.
+    async fn prune_completed_tasks(&self) {
+        let now = Instant::now();
+        let mut tracked_tasks = self.tracked_tasks.write().await;
+
+        let expired: Vec<u64> = tracked_tasks
+            .iter()
+            .filter(|(_, t)| {
+                t.completed_at
+                    .map_or(false, |at| now.saturating_duration_since(at) >= TRACKED_TASK_RETENTION)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        if expired.is_empty() {
+            return;
+        }
+
+        tracked_tasks.retain(|id, _| !expired.contains(id));
+        std::mem::drop(tracked_tasks);
+
+        self.uid_to_task
+            .write()
+            .await
+            .retain(|_, task_id| !expired.contains(task_id));
+    }
+
+    /// The soonest scheduled retry deadline across all tracked tasks, if any,
+    /// so the upsert worker's wake timer can wake up promptly for it instead
+    /// of waiting out the full adaptive interval or debounce window.
+    async fn next_retry_deadline(&self) -> Option<Instant> {
+        self.tracked_tasks
+            .read()
+            .await
+            .values()
+            .filter_map(|t| t.retry_at)
+            .min()
+    }
+
+    /// Re-dispatch every tracked task whose retry backoff has elapsed. Runs
+    /// from the upsert worker's main loop rather than inline with a
+    /// `tokio::time::sleep`, so one batch backed off for several seconds
+    /// can't stall polling, flushing, or the stop signal for everything else.
+    async fn dispatch_due_retries(&self, meili: &MeiliClient, followers: &[MeiliClient]) {
+        let now = Instant::now();
+        let due: Vec<u64> = self
+            .tracked_tasks
+            .read()
+            .await
+            .iter()
+            .filter(|(_, t)| t.retry_at.map_or(false, |at| at <= now))
+            .map(|(id, _)| *id)
+            .collect();
+
+        for task_id in due {
+            let Some((idx, ops)) = ({
+                let mut tracked_tasks = self.tracked_tasks.write().await;
+                tracked_tasks.get_mut(&task_id).map(|t| {
+                    t.retry_at = None;
+                    (t.idx.clone(), t.ops.clone())
+                })
+            }) else {
+                continue;
+            };
+
+            let result = self
+                .replicate(meili, followers, &idx, &ops, |client| {
+                    dispatch_ops(client, idx.clone(), ops.clone())
+                })
+                .await;
+
+            let mut tracked_tasks = self.tracked_tasks.write().await;
+            let Some(t) = tracked_tasks.get_mut(&task_id) else {
+                continue;
+            };
+
+            match result {
+                Ok(task) => {
+                    let uid = task_uid(&task);
+                    t.current_uid = uid;
+                    t.status = TaskStatus::Enqueued;
+                    std::mem::drop(tracked_tasks);
+                    self.uid_to_task.write().await.insert(uid, task_id);
+                },
+                Err(e) => {
+                    warn!("Retry dispatch for index {:?} failed: {}", idx, e);
+
+                    if t.retries >= self.max_task_retries {
+                        error!(
+                            "Giving up on index {:?} after {} retries",
+                            idx, t.retries
+                        );
+                        t.status = TaskStatus::Failed;
+                        t.completed_at = Some(Instant::now());
+                    } else {
+                        t.retries += 1;
+                        let backoff = self.task_retry_base_delay * 2u32.pow(t.retries - 1);
+                        t.retry_at = Some(Instant::now() + backoff);
+                    }
+                },
+            }
+        }
+    }
+
+    async fn track_task(&self, task: &Task, idx: String, ops: Vec<Operation>) {
+        let uid = task_uid(task);
+        let task_id = self.next_task_id.fetch_add(1, Ordering::Relaxed);
+
+        self.tracked_tasks.write().await.insert(task_id, TrackedTask {
+            idx,
+            ops,
+            status: TaskStatus::Enqueued,
+            retries: 0,
+            current_uid: uid,
+            retry_at: None,
+            completed_at: None,
+        });
+        self.uid_to_task.write().await.insert(uid, task_id);
+    }
+
+    /// Dispatch a write to the leader, then broadcast the same write to
+    /// every follower, buffering it for any follower that is unreachable or
+    /// whose task does not succeed so it can be replayed once that node
+    /// catches up. The leader is waited on and counted the same way as a
+    /// follower; if fewer nodes than `write_consistency` requires end up
+    /// acknowledging, that's logged rather than failing the call, since the
+    /// caller still needs the leader's task back to track and retry it (a
+    /// hard error here would otherwise mean the write is silently dropped
+    /// instead of entering the retry path).
+    ///
+    /// Errors if the leader could not be dispatched to, or if waiting on the
+    /// leader's task fails; no task is then handed back for the caller to
+    /// track.
+    async fn replicate<F>(
+        &self,
+        meili: &MeiliClient,
+        followers: &[MeiliClient],
+        idx: &str,
+        ops: &[Operation],
+        dispatch: F,
+    ) -> Result<Task>
+    where
+        F: for<'c> Fn(&'c MeiliClient) -> futures_util::future::BoxFuture<'c, Result<Task>>,
+    {
+        let enqueued = dispatch(meili).await.context("Leader dispatch failed")?;
+        let leader_task = meili
+            .wait_for_task(enqueued, None, None)
+            .await
+            .context("Leader task failed")?;
+        let mut acknowledged = usize::from(matches!(leader_task, Task::Succeeded { .. }));
+
+        for (i, follower) in followers.iter().enumerate() {
+            let succeeded = match dispatch(follower).await {
+                Ok(task) => matches!(
+                    follower.wait_for_task(task, None, None).await,
+                    Ok(Task::Succeeded { .. })
+                ),
+                Err(_) => false,
+            };
+
+            if succeeded {
+                acknowledged += 1;
+            } else {
+                self.buffer_for_follower(i, idx.to_owned(), ops).await;
+            }
+        }
+
+        let required = self.write_consistency.required(followers.len());
+        if acknowledged < required {
+            warn!(
+                "Only {}/{} required node(s) acknowledged the write to {:?}; leader task {:?} \
+                 will still be tracked for retry",
+                acknowledged, required, idx, leader_task
+            );
+        }
+
+        Ok(leader_task)
+    }
+
+    /// Dispatch `ops` to `idx` via [`Self::replicate`] and track the
+    /// resulting task, or, if `replicate` errors (the leader couldn't be
+    /// dispatched to, or its task couldn't be waited on), re-enqueue `ops`
+    /// for the next tick
+    /// instead of losing them. Used so a dispatch failure for one index
+    /// group doesn't abort the whole upsert tick, which runs several of
+    /// these concurrently.
+    async fn dispatch_and_track(
+        &self,
+        meili: &MeiliClient,
+        followers: &[MeiliClient],
+        idx: String,
+        ops: Vec<Operation>,
+    ) {
+        let result = self
+            .replicate(meili, followers, &idx, &ops, |client| {
+                dispatch_ops(client, idx.clone(), ops.clone())
+            })
+            .await;
+
+        match result {
+            Ok(task) => self.track_task(&task, idx, ops).await,
+            Err(e) => {
+                warn!(
+                    "Dispatch to index {:?} failed outright, re-enqueueing for the next tick: {}",
+                    idx, e
+                );
+
+                let queue = self.upsert_queue.read().await;
+                for op in ops {
+                    queue.push((idx.clone(), op));
+                }
+                std::mem::drop(queue);
+
+                *self.debounce_since.lock().await = Some(Instant::now());
+            },
+        }
+    }
+
+    async fn buffer_for_follower(&self, follower: usize, idx: String, ops: &[Operation]) {
+        let mut buffers = self.follower_buffers.lock().await;
+        if buffers.len() <= follower {
+            buffers.resize_with(follower + 1, HashMap::default);
+        }
+
+        let buffer = &mut buffers[follower];
+        for op in ops {
+            let key = follower_buffer_key(&idx, op);
+
+            if buffer.len() >= MAX_FOLLOWER_BUFFER_ENTRIES && !buffer.contains_key(&key) {
+                warn!(
+                    "Follower {} replay buffer full ({} entries); dropping update for {:?}",
+                    follower, MAX_FOLLOWER_BUFFER_ENTRIES, idx
+                );
+                continue;
+            }
+
+            buffer.insert(key, (idx.clone(), op.clone()));
+        }
+    }
+
+    /// Replay any operations buffered for followers that previously failed
+    /// or were unreachable, so a restarted/lagging node catches up without
+    /// reindexing from Postgres. Buffered writes are keyed and coalesced by
+    /// document (see [`follower_buffer_key`]), so replay can only ever apply
+    /// the latest queued write to a given document, never an older one.
+    async fn replay_follower_buffers(&self, followers: &[MeiliClient]) {
+        // Drain everything buffered under a briefly-held lock, then release
+        // it before issuing the replay I/O below. `buffer_for_follower` locks
+        // the same mutex on every `replicate` call on the hot dispatch path,
+        // so holding it across a network round-trip per buffered op here
+        // would stall normal dispatch for as long as replay takes.
+        let drained: Vec<_> = {
+            let mut buffers = self.follower_buffers.lock().await;
+            (0 .. followers.len())
+                .map(|i| {
+                    buffers
+                        .get_mut(i)
+                        .map(|buffer| buffer.drain().collect::<Vec<_>>())
+                        .unwrap_or_default()
+                })
+                .collect()
+        };
+
+        for (i, (follower, pending)) in followers.iter().zip(drained).enumerate() {
+            if pending.is_empty() {
+                continue;
+            }
+
+            let mut pending = pending.into_iter();
+            let mut remaining = Vec::new();
+
+            for (key, (idx, op)) in &mut pending {
+                let result = match &op {
+                    Operation::Upsert(doc) => {
+                        follower.index(&idx).add_or_replace(&[doc.clone()], None).await
+                    },
+                    Operation::Delete(pk) => {
+                        follower.index(&idx).delete_documents(&[pk.clone()]).await
+                    },
+                    Operation::SettingsUpdate(settings) => {
+                        follower.index(&idx).set_settings(settings).await
+                    },
+                };
+
+                if result.is_err() {
+                    // Leave this one and everything not yet attempted
+                    // buffered for the next pass.
+                    remaining.push((key, (idx, op)));
+                    break;
+                }
+            }
+
+            remaining.extend(pending);
+
+            if remaining.is_empty() {
+                continue;
+            }
+
+            let mut buffers = self.follower_buffers.lock().await;
+            if buffers.len() <= i {
+                buffers.resize_with(i + 1, HashMap::default);
+            }
+            let buffer = &mut buffers[i];
+            for (key, entry) in remaining {
+                buffer.insert(key, entry);
+            }
+        }
+    }
+
+    /// Get the last known status of a dispatched task, resolved by its
+    /// original uid even if it has since been retried under a new one.
+    ///
+    /// Returns `None` only if the uid is unknown to this client.
+    pub async fn task_status(&self, uid: u32) -> Option<TaskStatus> {
+        let task_id = *self.uid_to_task.read().await.get(&uid)?;
+        self.tracked_tasks.read().await.get(&task_id).map(|t| t.status)
+    }
+
+    /// List the current uids of all tasks not yet observed to succeed, i.e.
+    /// still enqueued, processing, or failed and awaiting/exhausted retry.
+    /// A failed task with retries exhausted drops out of this list once it
+    /// is evicted after `TRACKED_TASK_RETENTION`.
+    pub async fn pending_tasks(&self) -> Vec<u32> {
+        self.tracked_tasks
+            .read()
+            .await
+            .values()
+            .filter(|t| !matches!(t.status, TaskStatus::Succeeded))
+            .map(|t| t.current_uid)
+            .collect()
+    }
+
     async fn try_run_upserts(
         &self,
         meili: MeiliClient,
+        followers: &[MeiliClient],
         interval_sample_size: usize,
         batch_size: usize,
         dry_run: bool,
+        debounce_duration: Duration,
+        max_documents_per_batch: usize,
+        max_tasks_per_batch: usize,
         rx: &mut mpsc::Receiver<()>,
         mut stop_rx: &mut oneshot::Receiver<()>,
     ) -> Result<()> {
@@ -263,7 +977,7 @@ impl Client {
         let mut lock_if_stopping = None;
 
         let stop_reason = loop {
-            use futures_util::StreamExt;
+            use futures_util::{FutureExt, StreamExt};
 
             let interval = Self::update_upsert_interval(
                 &meili,
@@ -273,20 +987,42 @@ impl Client {
             )
             .await?;
 
+            self.reconcile_tracked_tasks(&meili, followers).await?;
+            self.replay_follower_buffers(followers).await;
+
+            // The wake deadline is the soonest of the adaptive interval, the
+            // debounce timer, and any pending retry backoff, so a just-arrived
+            // document or a due retry still gets serviced promptly without
+            // waiting out the full interval.
+            let mut wake_after = match *self.debounce_since.lock().await {
+                Some(since) => interval.min(
+                    debounce_duration.saturating_sub(Instant::now().saturating_duration_since(since)),
+                ),
+                None => interval,
+            };
+
+            if let Some(retry_at) = self.next_retry_deadline().await {
+                wake_after = wake_after.min(retry_at.saturating_duration_since(Instant::now()));
+            }
+
             let evt = tokio::select! {
                 o = rx.recv() => Event::Rx(o),
                 r = &mut stop_rx => Event::Stop(r),
-                () = tokio::time::sleep(interval) => Event::Tick,
+                () = tokio::time::sleep(wake_after) => Event::Tick,
             };
 
-            let stop_reason = match evt {
-                Event::Rx(Some(())) | Event::Tick => None,
-                Event::Rx(None) => Some("trigger event source closed"),
-                Event::Stop(Ok(())) => Some("stop signal received"),
+            // An explicit trigger means `enqueue` already saw the batch size
+            // reached, so it should flush immediately rather than wait out
+            // any remaining debounce window.
+            let (stop_reason, bypass_debounce) = match evt {
+                Event::Rx(Some(())) => (None, true),
+                Event::Tick => (None, false),
+                Event::Rx(None) => (Some("trigger event source closed"), false),
+                Event::Stop(Ok(())) => (Some("stop signal received"), false),
                 Event::Stop(Err(e)) => {
                     // Stoplight broke, stop anyway
                     error!("Failed to read upsert stop signal: {}", e);
-                    Some("error occurred reading stop signal")
+                    (Some("error occurred reading stop signal"), false)
                 },
             };
 
@@ -294,10 +1030,33 @@ impl Client {
             let mut lock = self.upsert_queue.write().await;
 
             if stop_reason.is_none() && lock.len() == 0 {
+                // The queue can end up empty with `debounce_since` still set
+                // (e.g. a flush drained it right after `enqueue` set the
+                // timer), which would otherwise wake this loop every
+                // debounce interval forever without ever having anything to
+                // flush.
+                *self.debounce_since.lock().await = None;
                 continue;
             }
 
+            if stop_reason.is_none() && !bypass_debounce {
+                let elapsed = self.debounce_since.lock().await.map_or(true, |since| {
+                    Instant::now().saturating_duration_since(since) >= debounce_duration
+                });
+
+                if !elapsed {
+                    // Woke early (the adaptive interval or a retry deadline
+                    // elapsed before the debounce window did); go back to
+                    // sleep without touching `debounce_since`, so the next
+                    // iteration's `wake_after` still targets the original
+                    // deadline instead of restarting the debounce window.
+                    std::mem::drop(lock);
+                    continue;
+                }
+            }
+
             let queue = std::mem::take(&mut *lock);
+            *self.debounce_since.lock().await = None;
 
             if stop_reason.is_none() {
                 std::mem::drop(lock);
@@ -313,28 +1072,115 @@ impl Client {
                     h
                 });
 
-            let mut futures = futures_util::stream::FuturesUnordered::new();
+            // Bound how many index groups get dispatched this tick; anything
+            // left over is pushed back onto the queue for the next one.
+            let mut entries: Vec<_> = map.into_iter().collect();
+            let deferred = if entries.len() > max_tasks_per_batch {
+                entries.split_off(max_tasks_per_batch)
+            } else {
+                Vec::new()
+            };
 
-            for (idx, docs) in &map {
+            if !deferred.is_empty() {
                 debug!(
-                    "{} document(s) in upsert queue flagged for {:?}",
-                    docs.len(),
+                    "Deferring {} index group(s) to the next tick (--max-tasks-per-batch)",
+                    deferred.len()
+                );
+
+                let requeue = self.upsert_queue.read().await;
+                for (idx, ops) in deferred {
+                    for op in ops {
+                        requeue.push((idx.clone(), op));
+                    }
+                }
+                std::mem::drop(requeue);
+
+                *self.debounce_since.lock().await = Some(Instant::now());
+            }
+
+            let mut futures: futures_util::stream::FuturesUnordered<
+                futures_util::future::BoxFuture<()>,
+            > = futures_util::stream::FuturesUnordered::new();
+
+            for (idx, ops) in &entries {
+                let mut upserts = Vec::new();
+                let mut deletes = Vec::new();
+                let mut settings_update = None;
+
+                for op in ops {
+                    match op {
+                        Operation::Upsert(doc) => upserts.push(doc.clone()),
+                        Operation::Delete(pk) => deletes.push(pk.clone()),
+                        Operation::SettingsUpdate(settings) => {
+                            settings_update = Some(settings.clone());
+                        },
+                    }
+                }
+
+                debug!(
+                    "{} upsert(s) and {} deletion(s) in upsert queue flagged for {:?}",
+                    upserts.len(),
+                    deletes.len(),
                     idx
                 );
 
                 if dry_run {
-                    info!("Upsert to {:?} of {:#?}", idx, serde_json::to_value(&docs));
-                } else {
-                    let meili = meili.clone();
-                    futures
-                        .push(async move { meili.index(idx).add_or_replace(&*docs, None).await });
+                    for chunk in upserts.chunks(max_documents_per_batch.max(1)) {
+                        info!("Upsert to {:?} of {:#?}", idx, serde_json::to_value(&chunk));
+                    }
+
+                    for chunk in deletes.chunks(max_documents_per_batch.max(1)) {
+                        info!("Delete from {:?} of {:#?}", idx, chunk);
+                    }
+
+                    if let Some(settings) = settings_update {
+                        info!("Settings update for {:?}: {:#?}", idx, settings);
+                    }
+
+                    continue;
                 }
-            }
 
-            while let Some(res) = futures.next().await {
-                res.context("Meilisearch API call failed")?;
+                // Dispatch deletes after upserts and the settings update
+                // last, and await each chunk before dispatching the next, so
+                // an upsert and a delete for the same key enqueued in the
+                // same tick (e.g. a burn racing a last-minute metadata
+                // update) can't land out of order. Indexes still dispatch
+                // concurrently with each other via `futures`.
+                let meili = meili.clone();
+                let idx = idx.clone();
+                let upsert_chunks: Vec<Vec<Operation>> = upserts
+                    .chunks(max_documents_per_batch.max(1))
+                    .map(|chunk| chunk.iter().cloned().map(Operation::Upsert).collect())
+                    .collect();
+                let delete_chunks: Vec<Vec<Operation>> = deletes
+                    .chunks(max_documents_per_batch.max(1))
+                    .map(|chunk| chunk.iter().cloned().map(Operation::Delete).collect())
+                    .collect();
+
+                futures.push(
+                    async move {
+                        for retry_ops in upsert_chunks {
+                            self.dispatch_and_track(&meili, followers, idx.clone(), retry_ops)
+                                .await;
+                        }
+
+                        for retry_ops in delete_chunks {
+                            self.dispatch_and_track(&meili, followers, idx.clone(), retry_ops)
+                                .await;
+                        }
+
+                        if let Some(settings) = settings_update {
+                            let retry_ops = vec![Operation::SettingsUpdate(settings)];
+                            self.dispatch_and_track(&meili, followers, idx, retry_ops)
+                                .await;
+                        }
+                    }
+                    .boxed(),
+                );
             }
 
+            while futures.next().await.is_some() {}
+
             if let Some(reason) = stop_reason {
                 break reason;
             }
@@ -356,17 +1202,235 @@ impl Client {
         &self.db
     }
 
-    /// Upsert a document to the `foo` index
+    /// Upsert a document to the given index
     ///
     /// # Errors
-    /// This function fails if the HTTP call returns an error
+    /// This function fails if the document(s) cannot be enqueued
     pub async fn upsert_documents<D: IntoIterator<Item = super::Document>>(
         &self,
         idx: String,
         docs: D,
     ) -> Result<()> {
+        self.enqueue(idx, docs.into_iter().map(Operation::Upsert))
+            .await
+    }
+
+    /// Delete documents with the given primary keys from an index, e.g. when
+    /// an NFT backing a `metadatas` or `name_service` record is burned
+    ///
+    /// # Errors
+    /// This function fails if the deletion(s) cannot be enqueued
+    pub async fn delete_documents<D: IntoIterator<Item = serde_json::Value>>(
+        &self,
+        idx: String,
+        primary_keys: D,
+    ) -> Result<()> {
+        self.enqueue(idx, primary_keys.into_iter().map(Operation::Delete))
+            .await
+    }
+
+    /// Queue a settings refresh for an index
+    ///
+    /// # Errors
+    /// This function fails if the update cannot be enqueued
+    pub async fn update_settings(&self, idx: String, settings: Settings) -> Result<()> {
+        self.enqueue(idx, std::iter::once(Operation::SettingsUpdate(settings)))
+            .await
+    }
+
+    /// Stream documents from `reader` into the same batched, interval-paced
+    /// upsert pipeline as [`Client::upsert_documents`], chunking by
+    /// `upsert_batch` and backpressuring the reader against `upsert_queue`
+    /// draining, so a large source accumulates in memory only a small
+    /// multiple of `upsert_batch` rather than in full. Useful for initial
+    /// backfills or re-syncs from a dump instead of the per-document
+    /// Postgres path.
+    ///
+    /// # Errors
+    /// This function fails if the reader cannot be read or a record fails
+    /// to parse or be enqueued.
+    pub async fn bulk_load<R: tokio::io::AsyncRead + Unpin + Send + 'static>(
+        &self,
+        idx: String,
+        reader: R,
+        content_type: ContentType,
+    ) -> Result<()> {
+        match content_type {
+            ContentType::NdJson => self.bulk_load_ndjson(idx, reader).await,
+            ContentType::Json => self.bulk_load_json(idx, reader).await,
+            ContentType::Csv => self.bulk_load_csv(idx, reader).await,
+        }
+    }
+
+    /// Enqueue a chunk read from a bulk-load source, then block the reader
+    /// until `upsert_queue` has drained back down. `enqueue` itself returns
+    /// immediately regardless of queue size, so without this a fast reader
+    /// would otherwise pile the whole source up in the (unbounded) queue
+    /// ahead of the upsert worker.
+    async fn flush_chunk(&self, idx: &str, chunk: &mut Vec<super::Document>) -> Result<()> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        self.upsert_documents(idx.to_owned(), std::mem::take(chunk))
+            .await?;
+
+        while self.upsert_queue.read().await.len() >= self.upsert_batch.max(1) * 2 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        Ok(())
+    }
+
+    async fn bulk_load_ndjson<R: tokio::io::AsyncRead + Unpin>(
+        &self,
+        idx: String,
+        reader: R,
+    ) -> Result<()> {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+        let mut chunk = Vec::with_capacity(self.upsert_batch);
+
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .context("Failed to read NDJSON line")?
+        {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let doc: super::Document =
+                serde_json::from_str(&line).context("Failed to parse NDJSON document")?;
+            chunk.push(doc);
+
+            if chunk.len() >= self.upsert_batch {
+                self.flush_chunk(&idx, &mut chunk).await?;
+            }
+        }
+
+        self.flush_chunk(&idx, &mut chunk).await
+    }
+
+    /// `serde_json` only exposes streaming deserialization synchronously, so
+    /// the reader is driven from a blocking task; the channel's bounded
+    /// capacity throttles how far parsing can run ahead of chunking, and
+    /// `flush_chunk`'s backpressure in turn throttles chunking against the
+    /// upsert worker draining `upsert_queue`, together keeping peak memory a
+    /// small multiple of `upsert_batch` rather than the size of the source.
+    async fn bulk_load_json<R: tokio::io::AsyncRead + Unpin + Send + 'static>(
+        &self,
+        idx: String,
+        reader: R,
+    ) -> Result<()> {
+        struct DocumentSink(mpsc::Sender<Result<super::Document>>);
+
+        impl<'de> serde::de::Visitor<'de> for &mut DocumentSink {
+            type Value = ();
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a JSON array of documents")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<(), A::Error> {
+                while let Some(doc) = seq.next_element::<super::Document>()? {
+                    if self.0.blocking_send(Ok(doc)).is_err() {
+                        break;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        let (tx, mut rx) = mpsc::channel(self.upsert_batch.max(1));
+
+        let parse = task::spawn_blocking(move || {
+            let mut sink = DocumentSink(tx.clone());
+            let mut de =
+                serde_json::Deserializer::from_reader(tokio_util::io::SyncIoBridge::new(reader));
+
+            if let Err(e) = de.deserialize_seq(&mut sink) {
+                let _ = tx.blocking_send(Err(
+                    anyhow::Error::new(e).context("Failed to parse JSON source")
+                ));
+            }
+        });
+
+        let mut chunk = Vec::with_capacity(self.upsert_batch);
+
+        while let Some(doc) = rx.recv().await {
+            chunk.push(doc?);
+
+            if chunk.len() >= self.upsert_batch {
+                self.flush_chunk(&idx, &mut chunk).await?;
+            }
+        }
+
+        self.flush_chunk(&idx, &mut chunk).await?;
+        parse.await.context("JSON parsing task panicked")?;
+
+        Ok(())
+    }
+
+    async fn bulk_load_csv<R: tokio::io::AsyncRead + Unpin + Send>(
+        &self,
+        idx: String,
+        reader: R,
+    ) -> Result<()> {
+        use futures_util::StreamExt;
+
+        let mut csv_reader = csv_async::AsyncReaderBuilder::new()
+            .has_headers(true)
+            .create_reader(reader);
+
+        // An optional `field:type` header (e.g. `price:float`) selects how a
+        // column is coerced; untyped columns are left as strings.
+        let (fields, types): (Vec<String>, Vec<Option<String>>) = csv_reader
+            .headers()
+            .await
+            .context("Failed to read CSV header row")?
+            .iter()
+            .map(|h| match h.split_once(':') {
+                Some((name, ty)) => (name.to_owned(), Some(ty.to_owned())),
+                None => (h.to_owned(), None),
+            })
+            .unzip();
+
+        let mut chunk = Vec::with_capacity(self.upsert_batch);
+        let mut records = csv_reader.records();
+
+        while let Some(record) = records.next().await {
+            let record = record.context("Failed to read CSV record")?;
+            chunk.push(csv_record_to_document(&fields, &types, &record)?);
+
+            if chunk.len() >= self.upsert_batch {
+                self.flush_chunk(&idx, &mut chunk).await?;
+            }
+        }
+
+        self.flush_chunk(&idx, &mut chunk).await
+    }
+
+    async fn enqueue<D: IntoIterator<Item = Operation>>(&self, idx: String, ops: D) -> Result<()> {
         let q = self.upsert_queue.read().await;
-        std::iter::repeat(idx).zip(docs).for_each(|p| q.push(p));
+        let was_empty = q.is_empty();
+        let mut pushed_any = false;
+        std::iter::repeat(idx).zip(ops).for_each(|p| {
+            q.push(p);
+            pushed_any = true;
+        });
+
+        if was_empty && pushed_any {
+            let mut debounce_since = self.debounce_since.lock().await;
+            if debounce_since.is_none() {
+                *debounce_since = Some(Instant::now());
+            }
+        }
 
         if q.len() >= self.upsert_batch {
             use mpsc::error::TrySendError;
@@ -382,7 +1446,12 @@ impl Client {
     }
 }
 
-async fn create_index(meili: MeiliClient, index_name: &str, primary_key: &str) -> Result<()> {
+async fn create_index(
+    meili: MeiliClient,
+    index_name: &str,
+    primary_key: &str,
+    settings: Option<&Settings>,
+) -> Result<()> {
     if let Ok(idx) = meili.get_index(index_name).await {
         ensure!(
             idx.get_primary_key()
@@ -397,5 +1466,96 @@ async fn create_index(meili: MeiliClient, index_name: &str, primary_key: &str) -
         meili.wait_for_task(task, None, None).await?;
     };
 
+    if let Some(desired) = settings {
+        let idx = meili
+            .index(index_name)
+            .get_settings()
+            .await
+            .context("Failed to fetch current index settings")?;
+
+        let current = serde_json::to_value(&idx)
+            .context("Failed to serialize current index settings")?;
+        let desired_value = serde_json::to_value(desired)
+            .context("Failed to serialize desired index settings")?;
+
+        // A settings descriptor only ever sets a handful of fields, leaving
+        // the rest `null`; the server's current settings always come back
+        // fully populated, so comparing the two directly would submit an
+        // update on every call. Treat `null` in `desired` as "no opinion" by
+        // overlaying just the fields it actually sets onto `current` before
+        // diffing.
+        let mut merged = current.clone();
+        if let (Some(merged), Some(desired)) = (merged.as_object_mut(), desired_value.as_object())
+        {
+            for (key, value) in desired {
+                if !value.is_null() {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        if merged != current {
+            debug!("Updating settings for index {:?}", index_name);
+
+            let task = meili
+                .index(index_name)
+                .set_settings(desired)
+                .await
+                .context("Failed to submit settings update")?;
+            meili.wait_for_task(task, None, None).await?;
+        }
+    }
+
     Ok(())
 }
+
+/// Settings applied to the `metadatas` index so location-bearing documents
+/// become geo-filterable and geo-sortable via a `_geo: { lat, lng }` field.
+///
+/// The `_geo` field itself must be populated on documents at the point they
+/// are constructed; that lives outside this module, wherever `super::Document`
+/// values are built for `metadatas`.
+fn metadatas_settings() -> Settings {
+    Settings {
+        filterable_attributes: Some(vec!["_geo".into()]),
+        sortable_attributes: Some(vec!["_geo".into()]),
+        ..Settings::default()
+    }
+}
+
+/// Build a document from one CSV record, coercing each field according to
+/// the optional `field:type` annotation on its header (`int`, `float`, or
+/// `bool`; anything else, including an absent type, is left as a string).
+fn csv_record_to_document(
+    fields: &[String],
+    types: &[Option<String>],
+    record: &csv_async::StringRecord,
+) -> Result<super::Document> {
+    let mut map = serde_json::Map::with_capacity(fields.len());
+
+    for ((field, ty), value) in fields.iter().zip(types.iter()).zip(record.iter()) {
+        let value = match ty.as_deref() {
+            Some("int") => serde_json::Value::from(
+                value
+                    .parse::<i64>()
+                    .with_context(|| format!("Failed to parse {field:?} as an integer"))?,
+            ),
+            Some("float") => serde_json::Value::from(
+                value
+                    .parse::<f64>()
+                    .with_context(|| format!("Failed to parse {field:?} as a float"))?,
+            ),
+            Some("bool") => serde_json::Value::from(
+                value
+                    .parse::<bool>()
+                    .with_context(|| format!("Failed to parse {field:?} as a bool"))?,
+            ),
+            _ => serde_json::Value::from(value),
+        };
+
+        map.insert(field.clone(), value);
+    }
+
+    serde_json::from_value(serde_json::Value::Object(map))
+        .context("Failed to convert CSV record into a document")
+}